@@ -1,10 +1,27 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use serde::Serialize;
 use tauri::api::dialog::FileDialogBuilder;
-use tauri::api::shell::Command; // CommandEvent is not used in the final version of run_cli_command
-use tauri::Manager; // For AppHandle
+use tauri::api::shell::{Command, CommandEvent};
+use tauri::Manager; // For emit_all
 // use std::path::PathBuf; // Not strictly needed after changes to get_cli_script_path
 
+/// Payload for the `cli-output` event emitted for each line a running
+/// sub-command writes to stdout/stderr.
+#[derive(Clone, Serialize)]
+struct CliOutputEvent {
+    sub_command: String,
+    stream: String,
+    line: String,
+}
+
+/// Payload for the `cli-output` event emitted once a sub-command exits.
+#[derive(Clone, Serialize)]
+struct CliTerminatedEvent {
+    sub_command: String,
+    code: Option<i32>,
+}
+
 #[tauri::command]
 async fn select_pst_file() -> Result<Option<String>, String> {
     let (sender, receiver) = std::sync::mpsc::channel();
@@ -60,9 +77,9 @@ async fn run_cli_command(app_handle: tauri::AppHandle, sub_command: String, args
     
     println!("Attempting to run command: '{}' with args: {:?}", python_interpreter, command_args);
 
-    // Spawn the command
-    let (_rx, child) = Command::new(python_interpreter) // Renamed mut rx to _rx as it's not used
-        .args(&command_args) 
+    // Spawn the command, keeping the event stream so we can forward output as it arrives
+    let (mut rx, _child) = Command::new(python_interpreter)
+        .args(&command_args)
         .spawn()
         .map_err(|e| {
             let err_msg = format!("Failed to spawn command '{}' with script '{}': {}. Check if Python is installed and the script path is correct.", python_interpreter, script_path, e);
@@ -70,27 +87,68 @@ async fn run_cli_command(app_handle: tauri::AppHandle, sub_command: String, args
             err_msg // Return error message to frontend
         })?;
 
-    // Wait for the command to complete and get all output
-    let output = child.wait_with_output().await.map_err(|e| format!("Failed to wait for command: {}", e))?;
+    // Still accumulate stdout so callers awaiting the promise get the final string
+    let mut stdout_buf = String::new();
+    let mut stderr_buf = String::new();
+    let mut exit_code: Option<i32> = None;
 
-    if output.status.success() {
-        let stdout_str = String::from_utf8_lossy(&output.stdout).to_string();
-        println!("CLI stdout:\n{}", stdout_str); // Log to Rust console
-        Ok(stdout_str) // Return stdout to frontend
-    } else {
-        let stderr_str = String::from_utf8_lossy(&output.stderr).to_string();
-        eprintln!("CLI stderr:\n{}", stderr_str); // Log to Rust console
-        // It's often useful to also include stdout in the error if stderr is empty but it failed
-        let stdout_str = String::from_utf8_lossy(&output.stdout).to_string();
-        if stderr_str.is_empty() && !stdout_str.is_empty() {
-             Err(format!("CLI command failed with status {}. Output:\n{}", output.status, stdout_str))
-        } else if stderr_str.is_empty() && stdout_str.is_empty() && !output.status.success() {
-             Err(format!("CLI command failed with status {} and no output.", output.status))
-        }
-        else {
-             Err(format!("CLI command failed with status {}. Error:\n{}", output.status, stderr_str))
+    while let Some(event) = rx.recv().await {
+        match event {
+            CommandEvent::Stdout(line) => {
+                println!("CLI stdout: {}", line); // Log to Rust console
+                stdout_buf.push_str(&line);
+                stdout_buf.push('\n');
+                let _ = app_handle.emit_all(
+                    "cli-output",
+                    CliOutputEvent {
+                        sub_command: sub_command.clone(),
+                        stream: "stdout".to_string(),
+                        line,
+                    },
+                );
+            }
+            CommandEvent::Stderr(line) => {
+                eprintln!("CLI stderr: {}", line); // Log to Rust console
+                stderr_buf.push_str(&line);
+                stderr_buf.push('\n');
+                let _ = app_handle.emit_all(
+                    "cli-output",
+                    CliOutputEvent {
+                        sub_command: sub_command.clone(),
+                        stream: "stderr".to_string(),
+                        line,
+                    },
+                );
+            }
+            CommandEvent::Error(err) => {
+                eprintln!("CLI spawn error: {}", err); // Log to Rust console
+                return Err(format!("CLI command failed: {}", err));
+            }
+            CommandEvent::Terminated(payload) => {
+                exit_code = payload.code;
+                let _ = app_handle.emit_all(
+                    "cli-output",
+                    CliTerminatedEvent {
+                        sub_command: sub_command.clone(),
+                        code: exit_code,
+                    },
+                );
+                break;
+            }
+            _ => {}
         }
     }
+
+    if exit_code == Some(0) {
+        Ok(stdout_buf) // Return accumulated stdout to frontend
+    } else if stderr_buf.is_empty() && !stdout_buf.is_empty() {
+        Err(format!("CLI command failed with status {:?}. Output:\n{}", exit_code, stdout_buf))
+    } else if stderr_buf.is_empty() && stdout_buf.is_empty() {
+        Err(format!("CLI command failed with status {:?} and no output.", exit_code))
+    }
+    else {
+        Err(format!("CLI command failed with status {:?}. Error:\n{}", exit_code, stderr_buf))
+    }
 }
 
 fn main() {
@@ -0,0 +1,45 @@
+// src/exclude.rs
+//
+// Gitignore-style exclusion matching. `--exclude` patterns support `*`,
+// `**`, leading-slash root anchoring, trailing-slash directory-only
+// patterns, and `!`-prefixed negation, with later patterns overriding
+// earlier ones exactly like a `.gitignore` file. This replaces the old
+// `path_str.contains(excluded)` substring check, which over-matched (e.g.
+// excluding `env` also excluded `environment-data/`).
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::Path;
+
+pub struct ExcludeMatcher {
+    gitignore: Gitignore,
+}
+
+impl ExcludeMatcher {
+    /// Compile `patterns` into a matcher that evaluates paths relative to `root`.
+    pub fn new(root: &Path, patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in patterns {
+            // Malformed individual patterns are skipped rather than failing the whole scan.
+            let _ = builder.add_line(None, pattern);
+        }
+
+        let gitignore = builder.build().unwrap_or_else(|_| Gitignore::empty());
+
+        ExcludeMatcher { gitignore }
+    }
+
+    /// Whether `path` (file or directory) is excluded, honoring negation rules.
+    /// Parent directories of `path` are checked too, so a file inside an
+    /// excluded directory is excluded. `path` must lie under the matcher's root.
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        self.gitignore
+            .matched_path_or_any_parents(path, is_dir)
+            .is_ignore()
+    }
+
+    /// Whether `path` is explicitly rescued by a `!`-negation pattern. Used
+    /// to let negation override hard-coded vetoes (like the hidden-directory
+    /// default) that would otherwise short-circuit ahead of the matcher.
+    pub fn is_whitelisted(&self, path: &Path, is_dir: bool) -> bool {
+        self.gitignore.matched(path, is_dir).is_whitelist()
+    }
+}
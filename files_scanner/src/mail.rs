@@ -0,0 +1,339 @@
+// src/mail.rs
+//
+// Cross-platform email store discovery. Mirrors the backend abstraction
+// used by the meli maildir code: a `MailBackend` trait that a concrete
+// store (Maildir, mbox, ...) implements to produce `EmailInfo`.
+use crate::{EmailInfo, FolderInfo, MessageInfo};
+use std::fs;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+/// Maildir message flags, encoded in the filename after the `:2,` suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Flags(u8);
+
+impl Flags {
+    pub const SEEN: Flags = Flags(1 << 0);
+    pub const REPLIED: Flags = Flags(1 << 1);
+    pub const FLAGGED: Flags = Flags(1 << 2);
+    pub const TRASHED: Flags = Flags(1 << 3);
+    pub const DRAFT: Flags = Flags(1 << 4);
+
+    fn insert(&mut self, other: Flags) {
+        self.0 |= other.0;
+    }
+
+    pub fn contains(self, other: Flags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+fn parse_flags(file_name: &str) -> Flags {
+    let mut flags = Flags::default();
+    let Some((_, suffix)) = file_name.split_once(":2,") else {
+        return flags;
+    };
+
+    for c in suffix.chars() {
+        match c {
+            'S' => flags.insert(Flags::SEEN),
+            'R' => flags.insert(Flags::REPLIED),
+            'F' => flags.insert(Flags::FLAGGED),
+            'T' => flags.insert(Flags::TRASHED),
+            'D' => flags.insert(Flags::DRAFT),
+            _ => {}
+        }
+    }
+
+    flags
+}
+
+/// A mail store that can be scanned into `EmailInfo`.
+pub trait MailBackend {
+    /// Human readable identifier for this backend, e.g. the mailbox owner's name.
+    fn name(&self) -> String;
+
+    /// Scan this backend's store and produce populated `EmailInfo`.
+    fn scan(&self) -> Vec<EmailInfo>;
+}
+
+/// A Maildir tree or mbox file discovered on disk.
+pub struct MaildirBackend {
+    root: PathBuf,
+}
+
+impl MaildirBackend {
+    pub fn new(root: PathBuf) -> Self {
+        MaildirBackend { root }
+    }
+
+    /// Discover Maildir trees and mbox files under the home directory,
+    /// descending up to `MAX_DISCOVERY_DEPTH` levels into non-hidden
+    /// directories to find layouts like `~/Mail/personal/INBOX` (as used by
+    /// offlineimap/mbsync/notmuch), not just direct children of home.
+    pub fn discover() -> Vec<MaildirBackend> {
+        let mut backends = Vec::new();
+
+        let Some(home) = dirs_next::home_dir() else {
+            return backends;
+        };
+
+        discover_in(&home, MAX_DISCOVERY_DEPTH, &mut backends);
+
+        backends
+    }
+}
+
+impl MailBackend for MaildirBackend {
+    fn name(&self) -> String {
+        self.root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.root.to_string_lossy().to_string())
+    }
+
+    fn scan(&self) -> Vec<EmailInfo> {
+        let folder = if is_mbox(&self.root) {
+            scan_mbox(&self.root)
+        } else {
+            scan_maildir_folder(&self.root, &self.name())
+        };
+
+        vec![EmailInfo {
+            name: self.name(),
+            folders: vec![folder],
+        }]
+    }
+}
+
+fn is_maildir(path: &Path) -> bool {
+    path.is_dir()
+        && path.join("cur").is_dir()
+        && path.join("new").is_dir()
+        && path.join("tmp").is_dir()
+}
+
+fn is_mbox(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("mbox"))
+            .unwrap_or(false)
+}
+
+/// How many levels below home `discover_in` will descend looking for mail
+/// stores, e.g. `~/Mail/personal/INBOX` is 2 levels down from `~/Mail`.
+const MAX_DISCOVERY_DEPTH: u32 = 3;
+
+/// Walk `dir` looking for Maildir trees and mbox files, descending into
+/// non-hidden subdirectories (so a found Maildir's own `.Subfolder`
+/// sub-mailboxes aren't re-discovered as separate top-level backends) up to
+/// `depth_remaining` levels.
+fn discover_in(dir: &Path, depth_remaining: u32, backends: &mut Vec<MaildirBackend>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+
+        if is_maildir(&path) || is_mbox(&path) {
+            backends.push(MaildirBackend::new(path));
+            continue;
+        }
+
+        if depth_remaining == 0 || !path.is_dir() {
+            continue;
+        }
+
+        let hidden = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.starts_with('.'))
+            .unwrap_or(false);
+
+        if !hidden {
+            discover_in(&path, depth_remaining - 1, backends);
+        }
+    }
+}
+
+/// Recursively scan a Maildir mailbox, counting `new/` + `cur/` entries and
+/// descending into Maildir++ sub-mailboxes (directories named `.Subfolder`).
+fn scan_maildir_folder(path: &Path, name: &str) -> FolderInfo {
+    let mut messages = Vec::new();
+    let mut item_count = 0i32;
+
+    for sub in ["new", "cur"] {
+        let Ok(entries) = fs::read_dir(path.join(sub)) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let entry_path = entry.path();
+            if !entry_path.is_file() {
+                continue;
+            }
+
+            item_count += 1;
+
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if parse_flags(&file_name).contains(Flags::TRASHED) {
+                continue;
+            }
+
+            messages.push(parse_envelope(&entry_path));
+        }
+    }
+
+    let mut subfolders = Vec::new();
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let entry_path = entry.path();
+            let Some(entry_name) = entry_path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+
+            if let Some(sub_name) = entry_name.strip_prefix('.') {
+                if !sub_name.is_empty() && is_maildir(&entry_path) {
+                    subfolders.push(scan_maildir_folder(&entry_path, sub_name));
+                }
+            }
+        }
+    }
+
+    FolderInfo {
+        name: name.to_string(),
+        item_count,
+        subfolders,
+        messages,
+    }
+}
+
+/// Scan an mbox file, treating each `From ` line as a message boundary and
+/// parsing only the header block (up to the first blank line) of each
+/// message. Reads line-by-line and decodes each line independently
+/// (`from_utf8_lossy`) rather than `read_to_string`-ing the whole archive, so
+/// non-UTF-8 bytes in a message body or attachment can't fail parsing of
+/// headers elsewhere in the mailbox.
+fn scan_mbox(path: &Path) -> FolderInfo {
+    let mut messages = Vec::new();
+
+    if let Ok(file) = fs::File::open(path) {
+        let mut reader = std::io::BufReader::new(file);
+        let mut raw_line = Vec::new();
+        let mut in_header = false;
+        let mut subject = String::new();
+        let mut from = String::new();
+        let mut date = String::new();
+
+        while let Some(line) = read_line_lossy(&mut reader, &mut raw_line) {
+            if line.starts_with("From ") {
+                flush_envelope(&mut subject, &mut from, &mut date, &mut messages);
+                in_header = true;
+                continue;
+            }
+
+            if !in_header {
+                continue;
+            }
+
+            if line.is_empty() {
+                in_header = false;
+                continue;
+            }
+
+            if let Some(value) = line.strip_prefix("Subject:") {
+                subject = value.trim().to_string();
+            } else if let Some(value) = line.strip_prefix("From:") {
+                from = value.trim().to_string();
+            } else if let Some(value) = line.strip_prefix("Date:") {
+                date = value.trim().to_string();
+            }
+        }
+
+        flush_envelope(&mut subject, &mut from, &mut date, &mut messages);
+    }
+
+    FolderInfo {
+        name: path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        item_count: messages.len() as i32,
+        subfolders: Vec::new(),
+        messages,
+    }
+}
+
+fn flush_envelope(
+    subject: &mut String,
+    from: &mut String,
+    date: &mut String,
+    messages: &mut Vec<MessageInfo>,
+) {
+    if subject.is_empty() && from.is_empty() && date.is_empty() {
+        return;
+    }
+
+    messages.push(MessageInfo {
+        subject: std::mem::take(subject),
+        from: std::mem::take(from),
+        date: std::mem::take(date),
+    });
+}
+
+/// Parse only the header block of a single Maildir message file (stop at the
+/// first blank line) to extract envelope fields cheaply. Reads line-by-line
+/// instead of `read_to_string`-ing the whole message, so non-UTF-8 bytes in
+/// the body or an attachment past the header can't affect this message's
+/// envelope (or any other message's).
+fn parse_envelope(path: &Path) -> MessageInfo {
+    let mut subject = String::new();
+    let mut from = String::new();
+    let mut date = String::new();
+
+    if let Ok(file) = fs::File::open(path) {
+        let mut reader = std::io::BufReader::new(file);
+        let mut raw_line = Vec::new();
+
+        while let Some(line) = read_line_lossy(&mut reader, &mut raw_line) {
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some(value) = line.strip_prefix("Subject:") {
+                subject = value.trim().to_string();
+            } else if let Some(value) = line.strip_prefix("From:") {
+                from = value.trim().to_string();
+            } else if let Some(value) = line.strip_prefix("Date:") {
+                date = value.trim().to_string();
+            }
+        }
+    }
+
+    MessageInfo { subject, from, date }
+}
+
+/// Read one line from `reader` into `raw_line` (reused across calls to avoid
+/// reallocating), decoding it lossily so a non-UTF-8 byte can't abort the
+/// whole read. Returns `None` at EOF or on an I/O error.
+fn read_line_lossy(reader: &mut impl BufRead, raw_line: &mut Vec<u8>) -> Option<String> {
+    raw_line.clear();
+    match reader.read_until(b'\n', raw_line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(
+            String::from_utf8_lossy(raw_line)
+                .trim_end_matches(['\r', '\n'])
+                .to_string(),
+        ),
+    }
+}
+
+/// Discover and scan all Maildir/mbox stores under the home directory.
+pub fn scan_mail() -> Vec<EmailInfo> {
+    MaildirBackend::discover()
+        .iter()
+        .flat_map(|backend| backend.scan())
+        .collect()
+}
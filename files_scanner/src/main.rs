@@ -1,5 +1,6 @@
 // src/main.rs
 use chrono::{DateTime, Local};
+use memmap2::Mmap;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -9,10 +10,18 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use structopt::StructOpt;
 use walkdir::{DirEntry, WalkDir};
+use xxhash_rust::xxh3::xxh3_64;
 
 #[cfg(target_os = "windows")]
 use outlook::scan_outlook;
 
+mod exclude;
+#[cfg(not(target_os = "windows"))]
+mod mail;
+mod watch;
+
+use exclude::ExcludeMatcher;
+
 #[derive(Debug, StructOpt)]
 #[structopt(
     name = "file-scanner",
@@ -34,6 +43,18 @@ struct Opt {
     /// Number of threads to use
     #[structopt(short, long, default_value = "0")]
     threads: usize,
+
+    /// Keep running after the initial scan, watching scan directories for changes
+    #[structopt(long)]
+    watch: bool,
+
+    /// Compute a content hash for each scanned file
+    #[structopt(long)]
+    hash: bool,
+
+    /// Group files with identical size and content hash into duplicate sets (implies --hash)
+    #[structopt(long)]
+    dedup: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -42,8 +63,19 @@ struct FileInfo {
     size: u64,
     modified: String,
     created: String,
+    content_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct DuplicateSet {
+    size: u64,
+    hash: String,
+    paths: Vec<String>,
 }
 
+/// Marker stored instead of a digest for zero-length files, which can't be mmap'd.
+const EMPTY_FILE_HASH: &str = "empty";
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct ScanResults {
     csv: Vec<FileInfo>,
@@ -64,6 +96,14 @@ struct FolderInfo {
     name: String,
     item_count: i32,
     subfolders: Vec<FolderInfo>,
+    messages: Vec<MessageInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct MessageInfo {
+    subject: String,
+    from: String,
+    date: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -80,6 +120,7 @@ struct Summary {
 struct OutputData {
     summary: Summary,
     results: ScanResults,
+    duplicates: Vec<DuplicateSet>,
 }
 
 fn get_default_scan_dirs() -> Vec<PathBuf> {
@@ -114,71 +155,69 @@ fn get_default_scan_dirs() -> Vec<PathBuf> {
     dirs
 }
 
+/// Default `--exclude` patterns, in gitignore syntax: a bare name like
+/// `node_modules/` matches a directory of that name at any depth (precisely,
+/// not as a substring), while `/` anchoring or `**` can be used by callers
+/// that pass their own `--exclude` list.
 fn get_default_exclude_dirs() -> Vec<String> {
     let mut exclude = vec![
-        "Windows".to_string(),
-        "Program Files".to_string(),
-        "Program Files (x86)".to_string(),
-        "ProgramData".to_string(),
-        "System Volume Information".to_string(),
-        "$Recycle.Bin".to_string(),
-        "$RECYCLE.BIN".to_string(),
-        "node_modules".to_string(),
-        "venv".to_string(),
-        ".venv".to_string(),
-        "env".to_string(),
-        ".env".to_string(),
-        "__pycache__".to_string(),
-        "AppData".to_string(),
-        "tmp".to_string(),
-        "temp".to_string(),
-        ".git".to_string(),
+        "Windows/".to_string(),
+        "Program Files/".to_string(),
+        "Program Files (x86)/".to_string(),
+        "ProgramData/".to_string(),
+        "System Volume Information/".to_string(),
+        "$Recycle.Bin/".to_string(),
+        "$RECYCLE.BIN/".to_string(),
+        "node_modules/".to_string(),
+        "venv/".to_string(),
+        ".venv/".to_string(),
+        "env/".to_string(),
+        ".env/".to_string(),
+        "__pycache__/".to_string(),
+        "AppData/".to_string(),
+        "tmp/".to_string(),
+        "temp/".to_string(),
+        ".git/".to_string(),
     ];
 
     #[cfg(target_os = "macos")]
     {
-        exclude.extend(vec!["Library".to_string(), "System".to_string()]);
+        exclude.extend(vec!["Library/".to_string(), "System/".to_string()]);
     }
 
     #[cfg(target_os = "linux")]
     {
         exclude.extend(vec![
-            "bin".to_string(),
-            "boot".to_string(),
-            "dev".to_string(),
-            "etc".to_string(),
-            "lib".to_string(),
-            "lib64".to_string(),
-            "proc".to_string(),
-            "sys".to_string(),
-            "var".to_string(),
+            "/bin/".to_string(),
+            "/boot/".to_string(),
+            "/dev/".to_string(),
+            "/etc/".to_string(),
+            "/lib/".to_string(),
+            "/lib64/".to_string(),
+            "/proc/".to_string(),
+            "/sys/".to_string(),
+            "/var/".to_string(),
         ]);
     }
 
     exclude
 }
 
-fn should_skip_dir(path: &Path, exclude_dirs: &[String]) -> bool {
+fn should_skip_dir(path: &Path, is_dir: bool, matcher: &ExcludeMatcher) -> bool {
     let dir_name = path
         .file_name()
         .unwrap_or_default()
         .to_string_lossy()
         .to_lowercase();
 
-    // Skip hidden directories
+    // Hidden directories are skipped by default, but an explicit `!`-negation
+    // pattern (e.g. `!.git/`) can still rescue one, so this veto doesn't
+    // silently break the "later rules override earlier matches" contract.
     if dir_name.starts_with('.') {
-        return true;
-    }
-
-    // Skip excluded directories
-    let path_str = path.to_string_lossy().to_lowercase();
-    for excluded in exclude_dirs {
-        if path_str.contains(&excluded.to_lowercase()) {
-            return true;
-        }
+        return !matcher.is_whitelisted(path, is_dir);
     }
 
-    false
+    matcher.is_excluded(path, is_dir)
 }
 
 fn is_target_file(path: &Path) -> Option<String> {
@@ -197,7 +236,11 @@ fn is_target_file(path: &Path) -> Option<String> {
     }
 }
 
-fn process_file(path: &Path) -> Option<(String, FileInfo)> {
+fn process_file(path: &Path, compute_hash: bool, matcher: &ExcludeMatcher) -> Option<(String, FileInfo)> {
+    if matcher.is_excluded(path, false) {
+        return None;
+    }
+
     let category = is_target_file(path)?;
 
     let metadata = match fs::metadata(path) {
@@ -221,16 +264,41 @@ fn process_file(path: &Path) -> Option<(String, FileInfo)> {
         Err(_) => "unknown".to_string(),
     };
 
+    let content_hash = if compute_hash {
+        hash_file(path, metadata.len())
+    } else {
+        None
+    };
+
     let file_info = FileInfo {
         path: path.to_string_lossy().to_string(),
         size: metadata.len(),
         modified,
         created,
+        content_hash,
     };
 
     Some((category, file_info))
 }
 
+/// mmap `path` and compute a fast, non-cryptographic digest over its bytes.
+/// Zero-length files can't be mmap'd on most platforms, so they get a fixed
+/// marker instead. Guards against the file changing size between the
+/// `fs::metadata` call in `process_file` and the mmap open here.
+fn hash_file(path: &Path, expected_len: u64) -> Option<String> {
+    if expected_len == 0 {
+        return Some(EMPTY_FILE_HASH.to_string());
+    }
+
+    let file = fs::File::open(path).ok()?;
+    if file.metadata().ok()?.len() != expected_len {
+        return None;
+    }
+
+    let mmap = unsafe { Mmap::map(&file).ok()? };
+    Some(format!("{:016x}", xxh3_64(&mmap)))
+}
+
 fn is_hidden_entry(entry: &DirEntry) -> bool {
     entry
         .file_name()
@@ -244,20 +312,28 @@ fn scan_directory(
     exclude_dirs: &[String],
     results: Arc<Mutex<ScanResults>>,
     file_count: Arc<Mutex<usize>>,
+    compute_hash: bool,
 ) {
+    let matcher = ExcludeMatcher::new(dir, exclude_dirs);
     let walker = WalkDir::new(dir).follow_links(false).into_iter();
 
-    // Filter out errors and apply exclusion rules
+    // Filter out errors and apply exclusion rules. Hidden entries are vetoed
+    // here before should_skip_dir runs, but (like should_skip_dir's own
+    // hidden-directory check) an explicit `!`-negation pattern can still
+    // rescue one, so this doesn't silently break gitignore negation semantics.
     let entries = walker
         .filter_entry(|e| {
-            !is_hidden_entry(e) && (e.path() == dir || !should_skip_dir(e.path(), exclude_dirs))
+            let is_dir = e.file_type().is_dir();
+            let rescued = is_hidden_entry(e) && matcher.is_whitelisted(e.path(), is_dir);
+            (!is_hidden_entry(e) || rescued)
+                && (e.path() == dir || !should_skip_dir(e.path(), is_dir, &matcher))
         })
         .filter_map(|e| e.ok())
         .filter(|e| e.file_type().is_file());
 
     // Process files in parallel
     entries.par_bridge().for_each(|entry| {
-        if let Some((category, file_info)) = process_file(entry.path()) {
+        if let Some((category, file_info)) = process_file(entry.path(), compute_hash, &matcher) {
             let mut results_lock = results.lock().unwrap();
             let mut count_lock = file_count.lock().unwrap();
 
@@ -274,9 +350,54 @@ fn scan_directory(
     });
 }
 
+fn category_counts(results: &ScanResults) -> HashMap<String, usize> {
+    let mut categories = HashMap::new();
+    categories.insert("csv".to_string(), results.csv.len());
+    categories.insert("excel".to_string(), results.excel.len());
+    categories.insert("text".to_string(), results.text.len());
+    categories.insert("json".to_string(), results.json.len());
+    categories.insert("email".to_string(), results.email.len());
+    categories
+}
+
+/// Group files with matching size and content hash into duplicate sets.
+/// Files without a hash (e.g. `--hash` was not passed) or marked as empty
+/// are excluded from grouping.
+fn find_duplicates(results: &ScanResults) -> Vec<DuplicateSet> {
+    let mut groups: HashMap<(u64, String), Vec<String>> = HashMap::new();
+
+    let files = results
+        .csv
+        .iter()
+        .chain(results.excel.iter())
+        .chain(results.text.iter())
+        .chain(results.json.iter());
+
+    for file in files {
+        let Some(hash) = &file.content_hash else {
+            continue;
+        };
+        if hash == EMPTY_FILE_HASH {
+            continue;
+        }
+
+        groups
+            .entry((file.size, hash.clone()))
+            .or_default()
+            .push(file.path.clone());
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((size, hash), paths)| DuplicateSet { size, hash, paths })
+        .collect()
+}
+
 fn save_results(
     results: &ScanResults,
     summary: &Summary,
+    duplicates: &[DuplicateSet],
     output_dir: &Path,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Create output directory if it doesn't exist
@@ -289,6 +410,7 @@ fn save_results(
     let output_data: OutputData = OutputData {
         summary: (*summary).clone(),
         results: (*results).clone(),
+        duplicates: duplicates.to_vec(),
     };
 
     // Save JSON results
@@ -349,7 +471,7 @@ mod outlook {
 
                     let email_info = EmailInfo {
                         name: profile_name,
-                        folders: Vec::new(), // We won't actually populate folder details for security reasons
+                        folders: Vec::new(), // Outlook profiles still need MAPI access to enumerate folders
                     };
 
                     results.push(email_info);
@@ -413,6 +535,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let file_count = Arc::new(Mutex::new(0));
 
+    // --dedup needs content hashes to group on
+    let compute_hash = opt.hash || opt.dedup;
+
     // Scan directories in parallel
     scan_dirs.par_iter().for_each(|dir| {
         if dir.exists() {
@@ -422,6 +547,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 &exclude_dirs,
                 Arc::clone(&results),
                 Arc::clone(&file_count),
+                compute_hash,
             );
         }
     });
@@ -433,6 +559,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         results_lock.email = scan_outlook();
     }
 
+    // Scan Maildir/mbox stores on macOS and Linux
+    #[cfg(not(target_os = "windows"))]
+    {
+        let mut results_lock = results.lock().unwrap();
+        results_lock.email = mail::scan_mail();
+    }
+
     // Calculate duration
     let duration = start_time.elapsed().as_secs_f64();
 
@@ -445,12 +578,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .map(|p| p.to_string_lossy().to_string())
         .collect();
 
-    let mut categories = HashMap::new();
-    categories.insert("csv".to_string(), result_data.csv.len());
-    categories.insert("excel".to_string(), result_data.excel.len());
-    categories.insert("text".to_string(), result_data.text.len());
-    categories.insert("json".to_string(), result_data.json.len());
-    categories.insert("email".to_string(), result_data.email.len());
+    let categories = category_counts(&result_data);
 
     let summary = Summary {
         timestamp: chrono::Local::now().to_rfc3339(),
@@ -461,12 +589,37 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         categories,
     };
 
+    // Group duplicates (requires --dedup; content hashes come from --hash/--dedup above)
+    let duplicates = if opt.dedup {
+        find_duplicates(&result_data)
+    } else {
+        Vec::new()
+    };
+
     // Save results
-    save_results(&result_data, &summary, &output_dir)?;
+    save_results(&result_data, &summary, &duplicates, &output_dir)?;
 
     println!("Scan completed in {:.2} seconds", duration);
     println!("Found {} files", total_count);
+    if opt.dedup {
+        println!("Found {} duplicate set(s)", duplicates.len());
+    }
     println!("Results saved to {}", output_dir.to_string_lossy());
 
+    drop(result_data);
+
+    if opt.watch {
+        watch::watch(
+            &scan_dirs,
+            &exclude_dirs,
+            Arc::clone(&results),
+            Arc::clone(&file_count),
+            summary,
+            &output_dir,
+            compute_hash,
+            opt.dedup,
+        )?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,282 @@
+// src/watch.rs
+//
+// `--watch` mode: after the initial scan, keep `ScanResults` live by
+// monitoring the scan directories with `notify` and re-saving `OutputData`
+// once a batch of filesystem events has settled.
+use crate::exclude::ExcludeMatcher;
+use crate::{
+    category_counts, find_duplicates, is_target_file, process_file, save_results, should_skip_dir,
+    FileInfo, ScanResults, Summary,
+};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PendingChange {
+    Upsert,
+    Remove,
+}
+
+/// Watch `scan_dirs` for changes, keeping `results`/`file_count` live and
+/// re-saving `OutputData` to `output_dir` on each settled batch. Runs until
+/// the watcher channel disconnects (i.e. for the lifetime of the process).
+pub fn watch(
+    scan_dirs: &[PathBuf],
+    exclude_dirs: &[String],
+    results: Arc<Mutex<ScanResults>>,
+    file_count: Arc<Mutex<usize>>,
+    summary: Summary,
+    output_dir: &Path,
+    compute_hash: bool,
+    dedup: bool,
+) -> notify::Result<()> {
+    let (tx, rx) = channel::<notify::Result<Event>>();
+    let mut watcher: RecommendedWatcher = notify::recommended_watcher(tx)?;
+
+    let matchers: Vec<(PathBuf, ExcludeMatcher)> = scan_dirs
+        .iter()
+        .map(|dir| (dir.clone(), ExcludeMatcher::new(dir, exclude_dirs)))
+        .collect();
+
+    for dir in scan_dirs {
+        watcher.watch(dir, RecursiveMode::Recursive)?;
+    }
+
+    println!(
+        "Watching {} director{} for changes (Ctrl+C to stop)...",
+        scan_dirs.len(),
+        if scan_dirs.len() == 1 { "y" } else { "ies" }
+    );
+
+    let mut pending: HashMap<PathBuf, PendingChange> = HashMap::new();
+    let mut last_event = Instant::now();
+
+    loop {
+        match rx.recv_timeout(Duration::from_millis(250)) {
+            Ok(Ok(event)) => {
+                record_event(&event.kind, &event.paths, &matchers, &mut pending);
+                last_event = Instant::now();
+            }
+            Ok(Err(err)) => eprintln!("Watch error: {}", err),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if !pending.is_empty() && last_event.elapsed() >= DEBOUNCE {
+            apply_batch(
+                std::mem::take(&mut pending),
+                &results,
+                &file_count,
+                compute_hash,
+                &matchers,
+            );
+            save_batch(&results, &file_count, &summary, output_dir, dedup);
+        }
+    }
+
+    Ok(())
+}
+
+/// Find the scan root and matcher for the root that contains `path`, if any.
+fn matcher_for<'a>(
+    path: &Path,
+    matchers: &'a [(PathBuf, ExcludeMatcher)],
+) -> Option<(&'a Path, &'a ExcludeMatcher)> {
+    matchers
+        .iter()
+        .find(|(root, _)| path.starts_with(root))
+        .map(|(root, matcher)| (root.as_path(), matcher))
+}
+
+/// Whether any path component between `root` and `path` (inclusive of the
+/// final component) is dot-prefixed. During the initial `WalkDir` scan,
+/// `should_skip_dir`'s hidden check only needs to look at the final
+/// component because `is_hidden_entry` vetoes every ancestor incrementally
+/// as the walk descends. A watch event, though, hands us the full path in
+/// one shot (e.g. `~/.cache/new.csv`), so a hidden ancestor that would have
+/// pruned the whole subtree up front has to be checked explicitly here. As in
+/// the initial scan, a hidden component rescued by a `!`-negation pattern
+/// does not count.
+fn has_hidden_ancestor(path: &Path, root: &Path, matcher: &ExcludeMatcher) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+
+    let mut current = root.to_path_buf();
+    relative.components().any(|component| {
+        current.push(component);
+        let hidden = component
+            .as_os_str()
+            .to_str()
+            .map(|name| name.starts_with('.'))
+            .unwrap_or(false);
+        hidden && !matcher.is_whitelisted(&current, current.as_path() != path)
+    })
+}
+
+/// Record the pending change(s) implied by one filesystem event. Most event
+/// kinds apply the same change to every path in the event (`notify` batches
+/// rename-from/rename-to into separate events on some platforms, each with
+/// one path). A same-directory rename, though, is commonly reported as a
+/// single `ModifyKind::Name(RenameMode::Both)` event carrying both the old
+/// and new path together (`event.paths == [from, to]`), so it needs
+/// per-path handling rather than one uniform change.
+fn record_event(
+    kind: &EventKind,
+    paths: &[PathBuf],
+    matchers: &[(PathBuf, ExcludeMatcher)],
+    pending: &mut HashMap<PathBuf, PendingChange>,
+) {
+    if let EventKind::Modify(ModifyKind::Name(RenameMode::Both)) = kind {
+        if let [from, to] = paths {
+            record_change(from.clone(), PendingChange::Remove, matchers, pending);
+            record_change(to.clone(), PendingChange::Upsert, matchers, pending);
+        } else {
+            // Platform didn't give us the expected from/to pair; treat every
+            // path as a fresh file rather than silently dropping the event.
+            for path in paths {
+                record_change(path.clone(), PendingChange::Upsert, matchers, pending);
+            }
+        }
+        return;
+    }
+
+    let change = match kind {
+        EventKind::Create(_) => PendingChange::Upsert,
+        EventKind::Modify(ModifyKind::Data(_)) => PendingChange::Upsert,
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => PendingChange::Upsert,
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => PendingChange::Remove,
+        EventKind::Remove(_) => PendingChange::Remove,
+        _ => return,
+    };
+
+    for path in paths {
+        record_change(path.clone(), change, matchers, pending);
+    }
+}
+
+fn record_change(
+    path: PathBuf,
+    change: PendingChange,
+    matchers: &[(PathBuf, ExcludeMatcher)],
+    pending: &mut HashMap<PathBuf, PendingChange>,
+) {
+    match matcher_for(&path, matchers) {
+        // Every event path is checked here as a file, directories included
+        // (process_file later filters by extension, so directory paths drop
+        // out in apply_batch). The hidden
+        // check also has to walk every ancestor, not just the final
+        // component, since `path` arrives as a full path rather than being
+        // vetted level-by-level the way the initial scan's WalkDir is.
+        Some((root, matcher))
+            if has_hidden_ancestor(&path, root, matcher)
+                || should_skip_dir(&path, false, matcher) =>
+        {
+            return
+        }
+        Some(_) => {}
+        None => return,
+    }
+
+    pending.insert(path, change);
+}
+
+fn apply_batch(
+    batch: HashMap<PathBuf, PendingChange>,
+    results: &Arc<Mutex<ScanResults>>,
+    file_count: &Arc<Mutex<usize>>,
+    compute_hash: bool,
+    matchers: &[(PathBuf, ExcludeMatcher)],
+) {
+    let mut results_lock = results.lock().unwrap();
+    let mut count_lock = file_count.lock().unwrap();
+
+    for (path, change) in batch {
+        let path_str = path.to_string_lossy().to_string();
+
+        match change {
+            PendingChange::Upsert => {
+                if is_target_file(&path).is_none() {
+                    continue;
+                }
+                let Some((_, matcher)) = matcher_for(&path, matchers) else {
+                    continue;
+                };
+                let Some((category, file_info)) = process_file(&path, compute_hash, matcher)
+                else {
+                    continue;
+                };
+                let Some(vec) = category_vec_mut(&mut results_lock, &category) else {
+                    continue;
+                };
+                match vec.iter_mut().find(|f| f.path == path_str) {
+                    Some(existing) => *existing = file_info,
+                    None => {
+                        vec.push(file_info);
+                        *count_lock += 1;
+                    }
+                }
+            }
+            PendingChange::Remove => {
+                for vec in [
+                    &mut results_lock.csv,
+                    &mut results_lock.excel,
+                    &mut results_lock.text,
+                    &mut results_lock.json,
+                ] {
+                    if let Some(pos) = vec.iter().position(|f| f.path == path_str) {
+                        vec.remove(pos);
+                        *count_lock = count_lock.saturating_sub(1);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn category_vec_mut<'a>(
+    results: &'a mut ScanResults,
+    category: &str,
+) -> Option<&'a mut Vec<FileInfo>> {
+    match category {
+        "csv" => Some(&mut results.csv),
+        "excel" => Some(&mut results.excel),
+        "text" => Some(&mut results.text),
+        "json" => Some(&mut results.json),
+        _ => None,
+    }
+}
+
+fn save_batch(
+    results: &Arc<Mutex<ScanResults>>,
+    file_count: &Arc<Mutex<usize>>,
+    summary: &Summary,
+    output_dir: &Path,
+    dedup: bool,
+) {
+    let result_data = results.lock().unwrap();
+    let total_count = *file_count.lock().unwrap();
+
+    let mut batch_summary = summary.clone();
+    batch_summary.timestamp = chrono::Local::now().to_rfc3339();
+    batch_summary.file_count = total_count;
+    batch_summary.categories = category_counts(&result_data);
+
+    let duplicates = if dedup {
+        find_duplicates(&result_data)
+    } else {
+        Vec::new()
+    };
+
+    if let Err(err) = save_results(&result_data, &batch_summary, &duplicates, output_dir) {
+        eprintln!("Failed to save watch results: {}", err);
+    }
+}